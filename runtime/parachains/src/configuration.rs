@@ -0,0 +1,56 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Configuration manager for the other parachains modules.
+//!
+//! It holds the current configuration, used by all other parachains modules, as an on-chain
+//! record so that it can be changed by governance without a runtime upgrade.
+
+use sp_std::prelude::*;
+use frame_support::{decl_storage, decl_module};
+use codec::{Encode, Decode};
+
+/// All configuration of the runtime that is used by the parachains consensus modules.
+#[derive(Clone, Encode, Decode, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct HostConfiguration<BlockNumber> {
+	/// The number of blocks, after a candidate under dispute was backed, that it may remain
+	/// pending availability before it is dropped for timing out. Candidates that are not under
+	/// dispute are not dropped.
+	pub availability_timeout: BlockNumber,
+}
+
+impl<BlockNumber: Default> Default for HostConfiguration<BlockNumber> {
+	fn default() -> Self {
+		HostConfiguration {
+			availability_timeout: Default::default(),
+		}
+	}
+}
+
+pub trait Trait: frame_system::Trait {}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Configuration {
+		/// The active host configuration. Modules read it as stored; a change takes effect the
+		/// next time it is read and is not deferred to a session boundary.
+		ActiveConfig get(fn config) config(): HostConfiguration<T::BlockNumber>;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: <T as frame_system::Trait>::Origin { }
+}
@@ -20,20 +20,23 @@
 //! as crafting transactions using the provisioner for slashing the validators on the wrong side.
 
 use sp_std::prelude::*;
+use sp_std::collections::btree_map::BTreeMap;
 use primitives::v1::{
-	ValidatorId, CandidateCommitments, CandidateDescriptor, ValidatorIndex, Id as ParaId,
-	AvailabilityBitfield as AvailabilityBitfield, SignedAvailabilityBitfields, SigningContext,
-	BackedCandidate, CoreIndex, GroupIndex, CommittedCandidateReceipt,
+	ValidatorId, ValidatorSignature, CandidateCommitments, CandidateDescriptor, ValidatorIndex,
+	Id as ParaId, AvailabilityBitfield as AvailabilityBitfield, SignedAvailabilityBitfields,
+	SigningContext, BackedCandidate, CoreIndex, GroupIndex, CommittedCandidateReceipt,
 	CandidateReceipt, HeadData,
 };
 use frame_support::{
 	decl_storage, decl_module, decl_error, decl_event, ensure, debug,
-	dispatch::DispatchResult, IterableStorageMap, weights::Weight, traits::Get,
+	dispatch::DispatchResult, storage::StorageMap, IterableStorageMap, weights::Weight,
 };
+use frame_system::ensure_signed;
+use sp_application_crypto::AppVerify;
 use codec::{Encode, Decode};
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use sp_staking::SessionIndex;
-use sp_runtime::{DispatchError, traits::{One, Saturating}};
+use sp_runtime::{DispatchError, traits::{Hash as HashT, One, Saturating}};
 
 use crate::{configuration, paras, scheduler::CoreAssignment};
 
@@ -54,14 +57,94 @@ pub struct CandidatePendingAvailability<H, N> {
 	core: CoreIndex,
 	/// The candidate descriptor.
 	descriptor: CandidateDescriptor<H>,
+	/// The commitments appended to the descriptor once the candidate was backed.
+	commitments: CandidateCommitments,
 	/// The received availability votes. One bit per validator.
 	availability_votes: BitVec<BitOrderLsb0, u8>,
+	/// The number of validators in the set that was active when this candidate was backed.
+	///
+	/// Snapshotted here because `availability_votes` is sized against that set, while the
+	/// active validator count can change by the time this candidate is checked again (e.g.
+	/// after a session rotation) — using the current count instead would misjudge the bitfield.
+	validator_count: u32,
 	/// The block number of the relay-parent of the receipt.
 	relay_parent_number: N,
 	/// The block number of the relay-chain block this was backed in.
 	backed_in_number: N,
 }
 
+/// A vote on the validity of a candidate, as recorded in the statement table.
+///
+/// Each variant carries the signature of the validator who cast it, so a stored vote is
+/// self-attributable evidence on its own.
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum ValidityVote {
+	/// An implicit vote, inherited from seconding/backing the candidate.
+	Issued(ValidatorSignature),
+	/// An explicit vote, from a dispute statement, that the candidate is valid.
+	Valid(ValidatorSignature),
+	/// An explicit vote, from a dispute statement, that the candidate is invalid.
+	Invalid(ValidatorSignature),
+}
+
+/// The statements collected so far for a single candidate under dispute.
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct CandidateData {
+	/// The group the candidate was assigned to.
+	group: GroupIndex,
+	/// The votes imported for this candidate, keyed by validator index.
+	validity_votes: BTreeMap<ValidatorIndex, ValidityVote>,
+}
+
+/// Misbehavior by a validator, detected while importing statements into the table.
+///
+/// Every variant retains both of the conflicting, signed votes, so the record is self-contained
+/// cryptographic proof that can be handed to the slashing path without any further lookups.
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum Misbehavior<Hash> {
+	/// A validator voted `Valid` and `Invalid` on the same candidate, in either order.
+	ValidityDoubleVote {
+		/// The candidate that was double-voted on.
+		candidate: Hash,
+		/// The validator who cast both votes.
+		validator: ValidatorIndex,
+		/// The first vote received, and its signature.
+		first: (ValidityVote, ValidatorSignature),
+		/// The conflicting vote received afterwards, and its signature.
+		second: (ValidityVote, ValidatorSignature),
+	},
+}
+
+/// A statement about the validity of a candidate, prior to attribution of a signature.
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum DisputeStatement {
+	/// Asserts that the candidate is valid.
+	Valid,
+	/// Asserts that the candidate is invalid.
+	Invalid,
+}
+
+/// A `DisputeStatement`, attributed to a validator by index and signed over a `SigningContext`
+/// derived from the current session and parent block.
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct SignedStatement<Hash> {
+	/// The candidate the statement pertains to.
+	candidate_hash: Hash,
+	/// The group the candidate was assigned to.
+	group: GroupIndex,
+	/// The statement itself.
+	statement: DisputeStatement,
+	/// The index, within the current validator set, of the validator who signed.
+	validator_index: ValidatorIndex,
+	/// The signature over `(statement, candidate_hash, group, validator_index, signing_context).encode()`.
+	signature: ValidatorSignature,
+}
+
 pub trait Trait:
 	frame_system::Trait + paras::Trait + configuration::Trait
 {
@@ -70,13 +153,21 @@ pub trait Trait:
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Dispute {
-		/// The vote of the selected validators.
-		ValidatorVotes: map hasher(twox_64_concat) ValidatorIndex
-			=> Option<bool>;
-
-		/// The commitments of candidates pending availability, by ParaId.
+		/// The statement table, tracking the votes collected so far for each candidate under
+		/// dispute, keyed by candidate hash.
+		CandidateVotes: map hasher(twox_64_concat) T::Hash
+			=> Option<CandidateData>;
+
+		/// Misbehavior detected while importing statements into the table, keyed by candidate
+		/// hash, and awaiting collection by `process_concluded`.
+		Misbehaviors: map hasher(twox_64_concat) T::Hash
+			=> Vec<Misbehavior<T::Hash>>;
+
+		/// Candidates pending availability, by ParaId, carrying their commitments plus the
+		/// availability bitfield and backing block number consumed by `initializer_finalize`
+		/// to time out disputed candidates that never become available.
 		PendingAvailabilityCommitments: map hasher(twox_64_concat) ParaId
-			=> Option<CandidateCommitments>;
+			=> Option<CandidatePendingAvailability<T::Hash, T::BlockNumber>>;
 
 		/// The current validators, by their parachain session keys.
 		Validators get(fn validators) config(validators): Vec<ValidatorId>;
@@ -89,10 +180,16 @@ decl_storage! {
 // Errors inform users that something went wrong.
 decl_error! {
 	pub enum Error for Module<T: Trait> {
-		/// Error Y.
-		Y,
-		/// Error X.
-		X,
+		/// A validator attempted to submit a second statement for a candidate it already
+		/// voted on.
+		DuplicateStatement,
+		/// Neither side of the dispute has reached the resolution threshold yet.
+		DisputeNotConcluded,
+		/// The claimed validator index is not present in the current validator set.
+		ValidatorIndexOutOfBounds,
+		/// The statement's signature does not match the claimed validator under the current
+		/// session's signing context.
+		InvalidSignature,
 	}
 }
 
@@ -105,6 +202,8 @@ decl_event! {
 		DisputeResolved(CandidateReceipt<Hash>, SessionIndex, BlockNumber),
 		/// A candidate timed out. []
 		DisputeTimedOut(CandidateReceipt<Hash>, HeadData),
+		/// A validator was caught voting on both sides of a dispute for the same candidate. []
+		DisputeMisbehavior(Hash, ValidatorIndex),
 	}
 }
 
@@ -114,6 +213,51 @@ decl_module! {
 		for enum Call where origin: <T as frame_system::Trait>::Origin
 	{
 		fn deposit_event() = default;
+
+		/// Report a signed dispute statement from a validator.
+		///
+		/// The signature is verified against the claimed validator's key, under the
+		/// `SigningContext` of the current session, before the statement is imported into the
+		/// statement table.
+		///
+		/// Weight accounts for a signature check plus a statement-table write; a zero weight
+		/// here would let an account resubmit the same signed statement for free.
+		#[weight = 100_000_000]
+		fn report_dispute(origin, statement: SignedStatement<T::Hash>) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let SignedStatement { candidate_hash, group, statement, validator_index, signature }
+				= statement;
+
+			let validator_id = Validators::get()
+				.get(validator_index.0 as usize)
+				.cloned()
+				.ok_or(Error::<T>::ValidatorIndexOutOfBounds)?;
+
+			let signing_context = SigningContext {
+				session_index: CurrentSessionIndex::get(),
+				parent_hash: <frame_system::Module<T>>::parent_hash(),
+			};
+
+			let payload = (
+				statement.clone(),
+				candidate_hash,
+				group,
+				validator_index,
+				signing_context,
+			).encode();
+			ensure!(signature.verify(&payload[..], &validator_id), Error::<T>::InvalidSignature);
+
+			let vote = match statement {
+				DisputeStatement::Valid => ValidityVote::Valid(signature),
+				DisputeStatement::Invalid => ValidityVote::Invalid(signature),
+			};
+
+			// a double-vote is recorded as misbehavior rather than as a dispatch error.
+			let _ = Self::import_statement(candidate_hash, group, validator_index, vote);
+
+			Ok(())
+		}
 	}
 }
 
@@ -123,7 +267,56 @@ impl<T: Trait> Module<T> {
 	pub(crate) fn initializer_initialize(_now: T::BlockNumber) -> Weight { 0 }
 
 	/// Block finalization logic, called by initializer.
-	pub(crate) fn initializer_finalize() { }
+	///
+	/// Times out disputed candidates pending availability: any candidate under dispute whose
+	/// `availability_votes` have not reached the `resolution_threshold` within
+	/// `HostConfiguration::availability_timeout` blocks of being backed is dropped, emitting
+	/// `DisputeTimedOut`.
+	/// Candidates that are not under dispute are left to keep waiting for availability, however
+	/// long that takes.
+	///
+	/// Note: nothing in this chunk of the runtime backs a candidate and inserts it into
+	/// `PendingAvailabilityCommitments` yet, so this path only fires once the inclusion pipeline
+	/// that populates it lands.
+	pub(crate) fn initializer_finalize() {
+		let now = <frame_system::Module<T>>::block_number();
+		let timeout = configuration::Module::<T>::config().availability_timeout;
+
+		let timed_out: Vec<(ParaId, CandidateReceipt<T::Hash>)> =
+			<PendingAvailabilityCommitments<T>>::iter()
+				.filter_map(|(para_id, pending)| {
+					let thresh = resolution_threshold(pending.validator_count as usize) as u32;
+					if pending.availability_votes.count_ones() as u32 >= thresh {
+						return None;
+					}
+					if now.saturating_sub(pending.backed_in_number) < timeout {
+						return None;
+					}
+
+					let receipt = CommittedCandidateReceipt {
+						descriptor: pending.descriptor,
+						commitments: pending.commitments,
+					}.to_plain();
+
+					// only candidates actually under dispute are evicted for failing to reach
+					// availability in time; an undisputed candidate just keeps waiting.
+					let candidate_hash = <T as frame_system::Trait>::Hashing::hash_of(&receipt);
+					if !CandidateVotes::<T>::contains_key(candidate_hash) {
+						return None;
+					}
+
+					Some((para_id, receipt))
+				})
+				.collect();
+
+		for (para_id, receipt) in timed_out {
+			PendingAvailabilityCommitments::<T>::remove(para_id);
+
+			// TODO: a timed-out candidate never became available, so there is no committed head
+			// to report here; surface the parent's head instead once it is threaded through.
+			Self::deposit_event(Event::<T>::DisputeTimedOut(receipt, HeadData::default()));
+		}
+	}
 
 	/// Handle an incoming session change.
 	pub(crate) fn initializer_on_new_session(
@@ -131,9 +324,85 @@ impl<T: Trait> Module<T> {
 	) {
 		// unlike most drain methods, drained elements are not cleared on `Drop` of the iterator
 		// and require consumption.
-		for _ in <ValidatorVotes>::drain() { }
+		for _ in <CandidateVotes<T>>::drain() { }
+		for _ in <Misbehaviors<T>>::drain() { }
+    }
+
+    /// Import a statement into the statement table, creating the candidate's entry if this is
+    /// the first vote received for it.
+    ///
+    /// A second vote from the same validator index for the same candidate is never allowed to
+    /// overwrite the first. If the two votes contradict each other (one `Valid`, one `Invalid`),
+    /// the first contradiction is kept as `Misbehavior::ValidityDoubleVote` evidence and a
+    /// `DisputeMisbehavior` event is deposited; resubmitting the same conflicting statement again
+    /// is a no-op, so repeatedly reporting it cannot grow the evidence without bound. Either way,
+    /// `Error::DuplicateStatement` is returned.
+    fn import_statement(
+        candidate_hash: T::Hash,
+        group: GroupIndex,
+        validator: ValidatorIndex,
+        vote: ValidityVote,
+    ) -> Result<(), DispatchError> {
+        let existing = CandidateVotes::<T>::mutate(candidate_hash, |maybe_data| {
+            let data = maybe_data.get_or_insert_with(|| CandidateData {
+                group,
+                validity_votes: BTreeMap::new(),
+            });
+
+            match data.validity_votes.get(&validator) {
+                Some(existing) => Some(existing.clone()),
+                None => {
+                    data.validity_votes.insert(validator, vote.clone());
+                    None
+                }
+            }
+        });
+
+        let existing = match existing {
+            Some(existing) => existing,
+            None => return Ok(()),
+        };
+
+        if Self::is_contradicting(&existing, &vote) {
+            let already_reported = Misbehaviors::<T>::get(candidate_hash).iter().any(|m| {
+                let Misbehavior::ValidityDoubleVote { validator: reported, .. } = m;
+                *reported == validator
+            });
+
+            if !already_reported {
+                Misbehaviors::<T>::mutate(candidate_hash, |records| {
+                    records.push(Misbehavior::ValidityDoubleVote {
+                        candidate: candidate_hash,
+                        validator,
+                        first: (existing.clone(), Self::signature_of(&existing)),
+                        second: (vote.clone(), Self::signature_of(&vote)),
+                    });
+                });
+
+                Self::deposit_event(Event::<T>::DisputeMisbehavior(candidate_hash, validator));
+            }
+        }
+
+        Err(Error::<T>::DuplicateStatement.into())
+    }
+
+    /// Whether two votes for the same candidate are in direct contradiction: one asserts the
+    /// candidate is valid, the other that it is invalid.
+    fn is_contradicting(a: &ValidityVote, b: &ValidityVote) -> bool {
+        match (a, b) {
+            (ValidityVote::Valid(_), ValidityVote::Invalid(_)) => true,
+            (ValidityVote::Invalid(_), ValidityVote::Valid(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Extract the signature carried by a vote.
+    fn signature_of(vote: &ValidityVote) -> ValidatorSignature {
+        match vote {
+            ValidityVote::Issued(sig) | ValidityVote::Valid(sig) | ValidityVote::Invalid(sig)
+                => sig.clone(),
+        }
     }
-    
 
     fn validators_pro() -> Vec<ValidatorId> {
         vec![] // TODO
@@ -148,19 +417,32 @@ impl<T: Trait> Module<T> {
         unimplemented!("");
     }
 
-    /// Check all of the known votes in storage for that block.
-    /// Returns `true`
-    fn count_pro_and_cons_votes(block: <T as frame_system::Trait>::Hash) -> DisputeVotes {
-        // TODO which votes to we count here?
-        // approval?
-        // backing?
-        // both?
-        DisputeVotes::default() // TODO
+    /// Tally the votes recorded in the statement table for the given candidate.
+    ///
+    /// An `Issued` or `Valid` vote counts towards `pro`; an `Invalid` vote counts towards `cons`.
+    fn count_pro_and_cons_votes(candidate_hash: <T as frame_system::Trait>::Hash) -> DisputeVotes {
+        let data = match CandidateVotes::<T>::get(candidate_hash) {
+            Some(data) => data,
+            None => return DisputeVotes::default(),
+        };
+
+        let (mut pro, mut cons) = (0u32, 0u32);
+        for vote in data.validity_votes.values() {
+            match vote {
+                ValidityVote::Issued(_) | ValidityVote::Valid(_) => pro += 1,
+                ValidityVote::Invalid(_) => cons += 1,
+            }
+        }
+
+        DisputeVotes { pro, cons }
     }
 
 
     /// Transplant a vote onto all other forks.
-    fn transplant_to(resolution: Resolution, active_heads: Vec<<T as frame_system::Trait>::Hash>) {
+    fn transplant_to(
+        resolution: Resolution<<T as frame_system::Trait>::Hash>,
+        active_heads: Vec<<T as frame_system::Trait>::Hash>,
+    ) {
 
     }
 
@@ -179,20 +461,20 @@ impl<T: Trait> Module<T> {
         // TODO ensure!(..), bounds unclear
 
         // number of _all_ validators
-        let all_validators = 10u32; // TODO disamibiguate
+        let all_validators = Validators::get().len();
         let DisputeVotes { pro, cons } = Self::count_pro_and_cons_votes(block_hash);
-        let thresh = resolution_threshold(all_validators.len()) as u32;
+        let thresh = resolution_threshold(all_validators) as u32;
         let (pro, cons) = (pro >= thresh, cons >= thresh);
 
+        // exactly one side reached the threshold, since `pro ^ cons` holds past this point.
         if !(pro ^ cons) {
-            return Err(Error::X)
-        } else if pro && cons {
-            unreachable!("The number of validators was correctly assessed. qed");
-        } else if !pro && !cons {
-            // nothing todo just yet
-            return Ok(())
+            return Err(Error::<T>::DisputeNotConcluded.into())
         }
 
+        // self-contained slashing evidence collected while importing statements, e.g. from
+        // validators caught voting on both sides of the dispute.
+        let misbehaviors = Misbehaviors::<T>::take(block_hash);
+
         let resolution = if cons {
             Self::extend_blacklist(&[block_hash]);
             // slash the other party
@@ -200,16 +482,16 @@ impl<T: Trait> Module<T> {
                 hash: block_hash,
                 to_punish: Self::validators_pro(),
                 was_truely_wrong: true,
+                misbehaviors,
             }
-        } else if pro {
+        } else {
             // slash the other party
             Resolution {
                 hash: block_hash,
                 to_punish: Self::validators_cons(),
                 was_truely_wrong: false,
+                misbehaviors,
             }
-        } else {
-            return Err(Error::Y)
         };
 
 
@@ -226,10 +508,11 @@ impl<T: Trait> Module<T> {
 }
 
 #[derive(Encode, Decode)]
-struct Resolution {
+struct Resolution<Hash> {
     hash: Hash, // hash of the storage root / state root this dispute was about
     was_truely_wrong: bool, // if the originally tagged as bad, was actually bad
     to_punish: Vec<ValidatorId>, // the validator party to slash
+    misbehaviors: Vec<Misbehavior<Hash>>, // self-contained slashing evidence gathered while importing statements
 }
 
 #[derive(Encode, Decode, Default)]
@@ -238,11 +521,10 @@ pub(crate) struct DisputeVotes {
     pub(crate) cons: u32,
 }
 
-/// Calculate the majority requred to sway in one way or another
+/// Calculate the majority requred to sway in one way or another: a strict (> 2/3) supermajority,
+/// so an exact two-thirds split is not enough to conclude a dispute.
 const fn resolution_threshold(n_validators: usize) -> usize {
-	let mut threshold = (n_validators * 2) / 3;
-	threshold += (n_validators * 2) % 3;
-	threshold
+	(n_validators * 2) / 3 + 1
 }
 
 #[cfg(test)]